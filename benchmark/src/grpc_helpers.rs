@@ -10,36 +10,133 @@ use admission_control_proto::proto::{
 };
 use client::AccountStatus;
 use failure::prelude::*;
+use flate2::{
+    write::{DeflateEncoder, GzEncoder},
+    Compression,
+};
 use futures::{
     stream::{self, Stream},
     Future,
 };
-use grpcio::{self, CallOption};
+use grpcio::{self, CallOption, CompressionAlgorithms};
 use logger::prelude::*;
 use proto_conv::{FromProto, IntoProto};
-use std::{collections::HashMap, slice::Chunks, thread, time};
+use protobuf::Message;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    slice::Chunks,
+    sync::Mutex,
+    thread,
+    time::{self, Instant},
+};
 use types::{
     account_address::AccountAddress,
     account_config::get_account_resource_or_default,
     get_with_proof::{RequestItem, ResponseItem, UpdateToLatestLedgerRequest},
+    transaction::SignedTransaction,
 };
 
-use crate::OP_COUNTER;
+use crate::{latency_histogram::LatencyHistogram, OP_COUNTER};
 
 /// Timeout duration for grpc call option.
 const GRPC_TIMEOUT_MS: u64 = 8_000;
-/// Duration to sleep between consecutive queries for accounts' sequence numbers.
+/// Starting (minimum) duration to wait between consecutive queries for an account's
+/// sequence number.
 const QUERY_SEQUENCE_NUMBERS_INTERVAL_US: u64 = 100;
-/// Max number of iterations to wait (using accounts' sequence number) for submitted
-/// TXNs to become committed.
+/// Cap on the per-account backoff interval in `sync_account_sequence_number`, so a
+/// straggler is still polled at a bounded rate.
+const MAX_QUERY_SEQUENCE_NUMBERS_INTERVAL_US: u64 = 100_000;
+/// Max number of query rounds actually issued against the validator (not idle
+/// backoff ticks) to wait for submitted TXNs to become committed.
 pub const MAX_WAIT_COMMIT_ITERATIONS: u64 = 10_000;
 
+/// gRPC message compression algorithm to use for AC calls. Defaults to `None`
+/// to preserve today's uncompressed behavior; pick `Gzip` or `Deflate` when
+/// running bandwidth-limited against a remote cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrpcCompression {
+    None,
+    Gzip,
+    Deflate,
+}
+
+impl Default for GrpcCompression {
+    fn default() -> Self {
+        GrpcCompression::None
+    }
+}
+
+impl GrpcCompression {
+    fn as_grpcio(self) -> Option<CompressionAlgorithms> {
+        match self {
+            GrpcCompression::None => None,
+            GrpcCompression::Gzip => Some(CompressionAlgorithms::GRPC_COMPRESS_GZIP),
+            GrpcCompression::Deflate => Some(CompressionAlgorithms::GRPC_COMPRESS_DEFLATE),
+        }
+    }
+}
+
 /// Return a parameter that controls how "patient" AC clients are,
-/// who are waiting the response from AC for this amount of time.
-fn get_default_grpc_call_option() -> CallOption {
-    CallOption::default()
+/// who are waiting the response from AC for this amount of time, and which
+/// message compression algorithm (if any) to apply to the call.
+fn get_default_grpc_call_option(compression: GrpcCompression) -> CallOption {
+    let option = CallOption::default()
         .wait_for_ready(true)
-        .timeout(std::time::Duration::from_millis(GRPC_TIMEOUT_MS))
+        .timeout(std::time::Duration::from_millis(GRPC_TIMEOUT_MS));
+    match compression.as_grpcio() {
+        Some(algorithm) => option.compression_algorithm(algorithm),
+        None => option,
+    }
+}
+
+/// Count a proto message's on-wire byte totals into `OP_COUNTER`: its
+/// serialized (uncompressed) size, and the size it would occupy after
+/// compressing with the selected algorithm, so the bandwidth win from
+/// compression is directly measurable rather than inferred.
+/// `get_default_grpc_call_option` applies the actual compression for the
+/// call; this re-compresses the same bytes only to measure the win, using
+/// the same algorithm (gzip or deflate) so the reported figure matches what
+/// actually goes on the wire. A no-op when `compression` is `None`, so the
+/// default, backward-compatible submit path never pays for an extra proto
+/// serialization it doesn't need.
+fn count_payload_bytes<M: Message>(counter_name: &str, compression: GrpcCompression, message: &M) {
+    if compression == GrpcCompression::None {
+        return;
+    }
+    let uncompressed = match message.write_to_bytes() {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to serialize {} for byte accounting: {:?}", counter_name, e);
+            return;
+        }
+    };
+    OP_COUNTER.inc_by(
+        &format!("{}.bytes_uncompressed", counter_name),
+        uncompressed.len() as i64,
+    );
+    let compressed = match compression {
+        GrpcCompression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&uncompressed).and_then(|_| encoder.finish())
+        }
+        GrpcCompression::Deflate => {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&uncompressed).and_then(|_| encoder.finish())
+        }
+        GrpcCompression::None => return,
+    };
+    match compressed {
+        Ok(compressed) => {
+            OP_COUNTER.inc_by(
+                &format!("{}.bytes_compressed", counter_name),
+                compressed.len() as i64,
+            );
+        }
+        Err(e) => {
+            error!("Failed to compress {} for byte accounting: {:?}", counter_name, e);
+        }
+    }
 }
 
 /// Divide generic items into a vector of chunks of nearly equal size.
@@ -52,56 +149,144 @@ pub fn divide_items<T>(items: &[T], num_chunks: usize) -> Chunks<T> {
     items.chunks(chunk_size)
 }
 
+/// Tracks the wall-clock instant at which each sender's transaction was
+/// submitted, keyed by sender and by the sequence number the sender will
+/// have once that transaction commits. `sync_account_sequence_number` calls
+/// `take_up_to` on every poll to resolve and drain latency for each
+/// transaction whose sequence number has since been observed committed, not
+/// only the one matching a sender's final target, so a multi-txn batch
+/// yields one sample per transaction instead of one per sender. Because
+/// resolution only happens when a poll observes the commit, recorded
+/// latency also includes the slack between polls: up to
+/// `MAX_QUERY_SEQUENCE_NUMBERS_INTERVAL_US` of backoff in
+/// `sync_account_sequence_number`, and up to the caller's interval between
+/// calls of `sync_account_sequence_number_once`.
+#[derive(Default)]
+pub struct SubmitTimeTracker {
+    submit_times: Mutex<HashMap<AccountAddress, BTreeMap<u64, Instant>>>,
+}
+
+/// How long an unresolved submit-time entry may linger before being pruned.
+/// Transactions AC permanently rejects never advance their sender's sequence
+/// number, so without this bound their entries would sit in `submit_times`
+/// for the rest of the run.
+const MAX_PENDING_SUBMIT_AGE: time::Duration = time::Duration::from_secs(60);
+
+impl SubmitTimeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn stamp(&self, sender: AccountAddress, committed_sequence_number: u64) {
+        self.submit_times
+            .lock()
+            .unwrap()
+            .entry(sender)
+            .or_insert_with(BTreeMap::new)
+            .insert(committed_sequence_number, Instant::now());
+    }
+
+    /// Drain and return the submit instants for every sequence number at or
+    /// below `observed_sequence_number` recorded for `sender`.
+    fn take_up_to(&self, sender: AccountAddress, observed_sequence_number: u64) -> Vec<Instant> {
+        let mut submit_times = self.submit_times.lock().unwrap();
+        let entries = match submit_times.get_mut(&sender) {
+            Some(entries) => entries,
+            None => return Vec::new(),
+        };
+        let still_pending = entries.split_off(&(observed_sequence_number + 1));
+        let resolved = std::mem::replace(entries, still_pending);
+        if entries.is_empty() {
+            submit_times.remove(&sender);
+        }
+        resolved.into_iter().map(|(_, instant)| instant).collect()
+    }
+
+    /// Drop entries older than `MAX_PENDING_SUBMIT_AGE` so permanently-unresolved
+    /// senders (e.g. ones with only rejected transactions) don't keep this map
+    /// growing for the life of a run.
+    fn prune_stale(&self) {
+        let mut submit_times = self.submit_times.lock().unwrap();
+        submit_times.retain(|_, entries| {
+            entries.retain(|_, instant| instant.elapsed() < MAX_PENDING_SUBMIT_AGE);
+            !entries.is_empty()
+        });
+    }
+}
+
 /// ---------------------------------------------------------- ///
 ///  Transaction async request and response handling helpers.  ///
 /// ---------------------------------------------------------- ///
 
 /// By checking 1) ac status, 2) vm status, and 3) mempool status, decide whether the reponse
-/// from AC is accepted. If not, classify what the error type is.
-fn check_ac_response(resp: &ProtoSubmitTransactionResponse) -> bool {
+/// from AC is accepted. If not, classify what the error type is. `endpoint` tags the counter
+/// with which AC client the response came from, so a slow or failing node is visible.
+fn check_ac_response(resp: &ProtoSubmitTransactionResponse, endpoint: usize) -> bool {
     if resp.has_ac_status() {
         let status = resp.get_ac_status().get_code();
         if status == AdmissionControlStatusCode::Accepted {
-            OP_COUNTER.inc(&format!("submit_txns.{:?}", status));
+            OP_COUNTER.inc(&format!("submit_txns.endpoint_{}.{:?}", endpoint, status));
             true
         } else {
-            OP_COUNTER.inc(&format!("submit_txns.{:?}", status));
-            error!("Request rejected by AC: {:?}", resp);
+            OP_COUNTER.inc(&format!("submit_txns.endpoint_{}.{:?}", endpoint, status));
+            error!("Request rejected by AC (endpoint {}): {:?}", endpoint, resp);
             false
         }
     } else if resp.has_vm_status() {
-        OP_COUNTER.inc(&format!("submit_txns.{:?}", resp.get_vm_status()));
-        error!("Request causes error on VM: {:?}", resp);
+        OP_COUNTER.inc(&format!(
+            "submit_txns.endpoint_{}.{:?}",
+            endpoint,
+            resp.get_vm_status()
+        ));
+        error!("Request causes error on VM (endpoint {}): {:?}", endpoint, resp);
         false
     } else if resp.has_mempool_status() {
         OP_COUNTER.inc(&format!(
-            "submit_txns.{:?}",
+            "submit_txns.endpoint_{}.{:?}",
+            endpoint,
             resp.get_mempool_status().get_code()
         ));
-        error!("Request causes error on mempool: {:?}", resp);
+        error!(
+            "Request causes error on mempool (endpoint {}): {:?}",
+            endpoint, resp
+        );
         false
     } else {
-        OP_COUNTER.inc("submit_txns.Unknown");
-        error!("Request rejected by AC for unknown error: {:?}", resp);
+        OP_COUNTER.inc(&format!("submit_txns.endpoint_{}.Unknown", endpoint));
+        error!(
+            "Request rejected by AC for unknown error (endpoint {}): {:?}",
+            endpoint, resp
+        );
         false
     }
 }
 
 /// Send TXN requests to AC async, wait for and check the responses from AC.
+/// All of `txn_requests` goes to the single AC endpoint `client`; `endpoint` tags
+/// per-endpoint counters so a slow or failing node is visible in `OP_COUNTER`.
 /// Return the responses of only accepted TXN requests.
 /// Ignore but count both gRPC-failed submissions and AC-rejected TXNs.
-pub fn submit_and_wait_txn_requests(
+fn submit_and_wait_txn_requests_to_endpoint(
     client: &AdmissionControlClient,
+    endpoint: usize,
     txn_requests: &[SubmitTransactionRequest],
+    submit_times: &SubmitTimeTracker,
+    compression: GrpcCompression,
 ) -> Vec<ProtoSubmitTransactionResponse> {
     let futures: Vec<_> = txn_requests
         .iter()
         .filter_map(|req| {
-            match client.submit_transaction_async_opt(&req, get_default_grpc_call_option()) {
+            if let Ok(signed_txn) = SignedTransaction::from_proto(req.get_transaction().clone()) {
+                submit_times.stamp(signed_txn.sender(), signed_txn.sequence_number() + 1);
+            }
+            count_payload_bytes("submit_txns", compression, req);
+            match client
+                .submit_transaction_async_opt(&req, get_default_grpc_call_option(compression))
+            {
                 Ok(future) => Some(future),
                 Err(e) => {
-                    OP_COUNTER.inc(&format!("submit_txns.{:?}", e));
-                    error!("Failed to send gRPC request: {:?}", e);
+                    OP_COUNTER.inc(&format!("submit_txns.endpoint_{}.{:?}", endpoint, e));
+                    error!("Failed to send gRPC request to endpoint {}: {:?}", endpoint, e);
                     None
                 }
             }
@@ -112,21 +297,73 @@ pub fn submit_and_wait_txn_requests(
         .wait()
         .filter_map(|future_result| match future_result {
             Ok(proto_resp) => {
-                if check_ac_response(&proto_resp) {
+                if check_ac_response(&proto_resp, endpoint) {
                     Some(proto_resp)
                 } else {
                     None
                 }
             }
             Err(e) => {
-                OP_COUNTER.inc(&format!("submit_txns.{:?}", e));
-                error!("Failed to receive gRPC response: {:?}", e);
+                OP_COUNTER.inc(&format!("submit_txns.endpoint_{}.{:?}", endpoint, e));
+                error!(
+                    "Failed to receive gRPC response from endpoint {}: {:?}",
+                    endpoint, e
+                );
                 None
             }
         })
         .collect()
 }
 
+/// Send TXN requests to AC async, splitting them into chunks with `divide_items`
+/// and handing the chunks round-robin to `clients`. The chunks are sent one
+/// endpoint after another: all responses for one chunk are awaited before the
+/// next chunk is sent, so the endpoints are not driven concurrently.
+/// Return the responses of only accepted TXN requests.
+pub fn submit_and_wait_txn_requests(
+    clients: &[AdmissionControlClient],
+    txn_requests: &[SubmitTransactionRequest],
+    submit_times: &SubmitTimeTracker,
+) -> Vec<ProtoSubmitTransactionResponse> {
+    submit_and_wait_txn_requests_with_compression(
+        clients,
+        txn_requests,
+        submit_times,
+        GrpcCompression::default(),
+    )
+}
+
+/// Same as `submit_and_wait_txn_requests`, but with an explicit message
+/// compression algorithm for the underlying gRPC calls.
+pub fn submit_and_wait_txn_requests_with_compression(
+    clients: &[AdmissionControlClient],
+    txn_requests: &[SubmitTransactionRequest],
+    submit_times: &SubmitTimeTracker,
+    compression: GrpcCompression,
+) -> Vec<ProtoSubmitTransactionResponse> {
+    if clients.is_empty() {
+        error!("submit_and_wait_txn_requests called with no AC clients");
+        return Vec::new();
+    }
+    divide_items(txn_requests, clients.len())
+        .enumerate()
+        .flat_map(|(chunk_index, chunk)| {
+            // `divide_items` can hand back more chunks than `clients.len()` when
+            // the split isn't even, so wrap the chunk index back onto the
+            // physical client it actually lands on for both dispatch and the
+            // per-endpoint counter label.
+            let endpoint = chunk_index % clients.len();
+            submit_and_wait_txn_requests_to_endpoint(
+                &clients[endpoint],
+                endpoint,
+                chunk,
+                submit_times,
+                compression,
+            )
+        })
+        .collect()
+}
+
 /// ------------------------------------------------------------ ///
 ///  Account state async request and response handling helpers.  ///
 /// ------------------------------------------------------------ ///
@@ -137,13 +374,15 @@ pub fn submit_and_wait_txn_requests(
 fn get_account_state_async(
     client: &AdmissionControlClient,
     address: AccountAddress,
+    compression: GrpcCompression,
 ) -> Result<impl Future<Item = (AccountAddress, ResponseItem), Error = failure::Error>> {
     let requested_item = RequestItem::GetAccountState { address };
     let requested_items = vec![requested_item];
     let req = UpdateToLatestLedgerRequest::new(0, requested_items);
     let proto_req = req.into_proto();
+    count_payload_bytes("get_account_state", compression, &proto_req);
     let ret = client
-        .update_to_latest_ledger_async_opt(&proto_req, get_default_grpc_call_option())?
+        .update_to_latest_ledger_async_opt(&proto_req, get_default_grpc_call_option(compression))?
         .then(move |account_state_proof_resp| {
             // Instead of convert entire account_state_proof_resp to UpdateToLatestLedgerResponse,
             // directly get the ResponseItems and convert only first item to rust struct.
@@ -170,23 +409,27 @@ fn handle_account_state_response(resp: ResponseItem) -> Result<(u64, AccountStat
     }
 }
 
-/// Request a bunch of accounts' states, including sequence numbers and status from validator.
-/// Ignore any failure, during either requesting or processing, and continue for next account.
-/// Return the mapping from address to (sequence number, account status) tuple
-/// for all successfully requested accounts.
-pub fn get_account_states(
+/// Query a chunk of accounts' states against a single AC endpoint.
+fn get_account_states_from_endpoint(
     client: &AdmissionControlClient,
+    endpoint: usize,
     addresses: &[AccountAddress],
+    compression: GrpcCompression,
 ) -> HashMap<AccountAddress, (u64, AccountStatus)> {
     let futures: Vec<_> = addresses
         .iter()
-        .filter_map(|address| match get_account_state_async(client, *address) {
-            Ok(future) => Some(future),
-            Err(e) => {
-                error!("Failed to send account request: {:?}", e);
-                None
-            }
-        })
+        .filter_map(
+            |address| match get_account_state_async(client, *address, compression) {
+                Ok(future) => Some(future),
+                Err(e) => {
+                    error!(
+                        "Failed to send account request to endpoint {}: {:?}",
+                        endpoint, e
+                    );
+                    None
+                }
+            },
+        )
         .collect();
     let future_stream = stream::futures_unordered(futures);
     // Collect successfully requested account states.
@@ -206,20 +449,140 @@ pub fn get_account_states(
                 }
             },
             Err(e) => {
-                error!("Failed to receive account response: {:?}", e);
+                error!(
+                    "Failed to receive account response from endpoint {}: {:?}",
+                    endpoint, e
+                );
             }
         }
     }
     states
 }
 
+/// Request a bunch of accounts' states, including sequence numbers and status from validator.
+/// `addresses` is partitioned round-robin across `clients` so sequence-number sync spreads
+/// across endpoints instead of hitting a single AC node.
+/// Ignore any failure, during either requesting or processing, and continue for next account.
+/// Return the mapping from address to (sequence number, account status) tuple
+/// for all successfully requested accounts.
+pub fn get_account_states(
+    clients: &[AdmissionControlClient],
+    addresses: &[AccountAddress],
+) -> HashMap<AccountAddress, (u64, AccountStatus)> {
+    get_account_states_with_compression(clients, addresses, GrpcCompression::default())
+}
+
+/// Same as `get_account_states`, but with an explicit message compression
+/// algorithm for the underlying gRPC calls.
+pub fn get_account_states_with_compression(
+    clients: &[AdmissionControlClient],
+    addresses: &[AccountAddress],
+    compression: GrpcCompression,
+) -> HashMap<AccountAddress, (u64, AccountStatus)> {
+    if clients.is_empty() {
+        error!("get_account_states called with no AC clients");
+        return HashMap::new();
+    }
+    divide_items(addresses, clients.len())
+        .enumerate()
+        .flat_map(|(chunk_index, chunk)| {
+            // See the matching comment in `submit_and_wait_txn_requests_with_compression`:
+            // wrap the chunk index back onto the physical client it dispatches to.
+            let endpoint = chunk_index % clients.len();
+            get_account_states_from_endpoint(&clients[endpoint], endpoint, chunk, compression)
+        })
+        .collect()
+}
+
+/// Per-account adaptive polling state used by `sync_account_sequence_number`: how
+/// long to wait before the next query for this sender, doubled (up to a cap) after
+/// each observation that finds its sequence number unchanged, and reset to the
+/// minimum whenever its sequence number advances.
+struct BackoffState {
+    next_poll_at: Instant,
+    interval_us: u64,
+}
+
+impl BackoffState {
+    fn new() -> Self {
+        Self {
+            next_poll_at: Instant::now(),
+            interval_us: QUERY_SEQUENCE_NUMBERS_INTERVAL_US,
+        }
+    }
+
+    fn is_due(&self) -> bool {
+        Instant::now() >= self.next_poll_at
+    }
+
+    fn on_unchanged(&mut self) {
+        self.interval_us =
+            std::cmp::min(self.interval_us * 2, MAX_QUERY_SEQUENCE_NUMBERS_INTERVAL_US);
+        self.next_poll_at = Instant::now() + time::Duration::from_micros(self.interval_us);
+    }
+
+    fn on_advanced(&mut self) {
+        self.interval_us = QUERY_SEQUENCE_NUMBERS_INTERVAL_US;
+        self.next_poll_at = Instant::now() + time::Duration::from_micros(self.interval_us);
+    }
+}
+
+/// Resolve submit-to-commit latency into `histogram` for every transaction whose
+/// sequence number is covered by this round's observed `states`, via `submit_times`.
+/// Shared by the blocking, target-seeking poll loop in `sync_account_sequence_number`
+/// and the non-blocking one-shot `sync_account_sequence_number_once`.
+fn record_resolved_latencies(
+    states: &HashMap<AccountAddress, (u64, AccountStatus)>,
+    submit_times: &SubmitTimeTracker,
+    histogram: &LatencyHistogram,
+) {
+    for (address, (sequence_number, _status)) in states.iter() {
+        for submit_instant in submit_times.take_up_to(*address, *sequence_number) {
+            histogram.record(submit_instant.elapsed().as_micros() as u64);
+        }
+    }
+}
+
+/// Non-blocking, one-shot counterpart to `sync_account_sequence_number`: query
+/// `addresses`' current sequence numbers exactly once, with no retry, no backoff
+/// and no waiting for a target to be reached, resolving submit-to-commit latency
+/// for any transaction observed committed along the way. Intended for a periodic
+/// resync under sustained load, where the target-seeking poll loop would otherwise
+/// block the caller for up to `MAX_WAIT_COMMIT_ITERATIONS` rounds every time the
+/// emitter is still outpacing commits, which is the common case under load.
+pub fn sync_account_sequence_number_once(
+    clients: &[AdmissionControlClient],
+    addresses: &[AccountAddress],
+    submit_times: &SubmitTimeTracker,
+    histogram: &LatencyHistogram,
+) -> HashMap<AccountAddress, u64> {
+    let states = get_account_states(clients, addresses);
+    record_resolved_latencies(&states, submit_times, histogram);
+    states
+        .into_iter()
+        .map(|(address, (sequence_number, _status))| (address, sequence_number))
+        .collect()
+}
+
 /// For each sender account, synchronize its persisted sequence number from validator.
 /// When this sync sequence number equals the account's local sequence number,
 /// all its transactions are committed. Timeout if such condition is never met for all senders.
+/// Resolve and record submit-to-commit latency into `histogram` for every transaction
+/// observed committed this poll (via `submit_times.take_up_to`), not just the one at a
+/// sender's final target, so a multi-txn batch yields one sample per transaction.
+/// Rather than re-querying every unfinished sender every tick, each sender's poll
+/// interval backs off exponentially while its sequence number is unchanged and
+/// resets to the minimum once it advances, so fast-committing accounts stay
+/// responsive while stragglers collapse query volume. Because latency is only
+/// resolved once a poll observes the commit, recorded samples include up to that
+/// sender's current backoff interval (capped at `MAX_QUERY_SEQUENCE_NUMBERS_INTERVAL_US`)
+/// of slack.
 /// Return sender accounts' most recent persisted sequence numbers.
 pub fn sync_account_sequence_number(
-    client: &AdmissionControlClient,
+    clients: &[AdmissionControlClient],
     senders_and_sequence_numbers: &[(AccountAddress, u64)],
+    submit_times: &SubmitTimeTracker,
+    histogram: &LatencyHistogram,
 ) -> HashMap<AccountAddress, u64> {
     // Invariants for the keys in targets (T), unfinished (U) and finished (F):
     // (1) T = U union F, and (2) U and F are disjoint.
@@ -229,36 +592,72 @@ pub fn sync_account_sequence_number(
         .iter()
         .map(|(sender, _)| (*sender, 0))
         .collect();
+    let mut backoff: HashMap<AccountAddress, BackoffState> = senders_and_sequence_numbers
+        .iter()
+        .map(|(sender, _)| (*sender, BackoffState::new()))
+        .collect();
     let mut finished = HashMap::new();
+    let mut total_queries_issued: u64 = 0;
+    let mut last_prune = Instant::now();
 
-    let mut num_iters = 0;
-    while num_iters < MAX_WAIT_COMMIT_ITERATIONS {
-        let unfinished_addresses: Vec<_> = unfinished.keys().copied().collect();
-        let states = get_account_states(client, &unfinished_addresses);
-        for (address, (sequence_number, _status)) in states.iter() {
-            if let Some(target) = targets.get(address) {
-                if sequence_number == target {
-                    debug!("All TXNs from {:?} are committed", address);
-                    finished.insert(*address, *sequence_number);
-                    unfinished.remove(address);
-                } else {
-                    debug!(
-                        "{} TXNs from {:?} still uncommitted",
-                        target - sequence_number,
-                        address
-                    );
-                    unfinished.insert(*address, *sequence_number);
+    // Counts actual query rounds issued against the validator, not idle sleep
+    // ticks, so a straggler sitting on a long backoff interval still gets the
+    // full `MAX_WAIT_COMMIT_ITERATIONS` worth of real polls rather than being
+    // timed out after ~`MAX_WAIT_COMMIT_ITERATIONS * QUERY_SEQUENCE_NUMBERS_INTERVAL_US`
+    // of wall clock, most of which would otherwise be spent idling in backoff.
+    let mut num_query_rounds = 0;
+    while num_query_rounds < MAX_WAIT_COMMIT_ITERATIONS && !unfinished.is_empty() {
+        let due_addresses: Vec<_> = unfinished
+            .keys()
+            .copied()
+            .filter(|address| backoff.get(address).map_or(true, BackoffState::is_due))
+            .collect();
+        if !due_addresses.is_empty() {
+            total_queries_issued += due_addresses.len() as u64;
+            let states = get_account_states(clients, &due_addresses);
+            record_resolved_latencies(&states, submit_times, histogram);
+            for (address, (sequence_number, _status)) in states.iter() {
+                if let Some(target) = targets.get(address) {
+                    if sequence_number == target {
+                        debug!("All TXNs from {:?} are committed", address);
+                        finished.insert(*address, *sequence_number);
+                        unfinished.remove(address);
+                        backoff.remove(address);
+                    } else {
+                        debug!(
+                            "{} TXNs from {:?} still uncommitted",
+                            target - sequence_number,
+                            address
+                        );
+                        let previous_sequence_number = unfinished.insert(*address, *sequence_number);
+                        let state = backoff.entry(*address).or_insert_with(BackoffState::new);
+                        if previous_sequence_number == Some(*sequence_number) {
+                            state.on_unchanged();
+                        } else {
+                            state.on_advanced();
+                        }
+                    }
                 }
             }
+            num_query_rounds += 1;
         }
-        if finished.len() == senders_and_sequence_numbers.len() {
-            break;
+        if last_prune.elapsed() >= time::Duration::from_secs(10) {
+            submit_times.prune_stale();
+            last_prune = Instant::now();
         }
         thread::sleep(time::Duration::from_micros(
             QUERY_SEQUENCE_NUMBERS_INTERVAL_US,
         ));
-        num_iters += 1;
     }
+    OP_COUNTER.inc_by(
+        "sync_account_sequence_number.queries_issued",
+        total_queries_issued as i64,
+    );
+    debug!(
+        "sync_account_sequence_number issued {} state queries for {} senders",
+        total_queries_issued,
+        senders_and_sequence_numbers.len()
+    );
     // Merging won't have conflict because F and U are disjoint.
     finished.extend(unfinished);
     finished
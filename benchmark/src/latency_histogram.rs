@@ -0,0 +1,207 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A lock-free, log-bucketed latency histogram used to summarize
+//! submit-to-commit latencies observed during a benchmark run.
+//!
+//! Buckets are indexed by `floor(log2(latency_us))`, further split into a
+//! fixed number of linear sub-buckets within each power-of-two range. This
+//! bounds the relative error of any reported percentile to roughly 1%,
+//! while keeping the backing storage a fixed-size array of atomics so that
+//! `record` never blocks and can be called concurrently from many worker
+//! threads.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Number of linear sub-buckets per power-of-two range. With 128 sub-buckets
+/// per octave, each sub-bucket spans about 0.8% of its octave, which bounds
+/// the error of any reported percentile to about 1%.
+const SUB_BUCKETS_PER_OCTAVE: usize = 128;
+/// Number of octaves tracked, covering latencies up to 2^40 microseconds
+/// (about 12.7 days), which is more than enough headroom for a stalled run.
+const NUM_OCTAVES: usize = 40;
+const NUM_BUCKETS: usize = NUM_OCTAVES * SUB_BUCKETS_PER_OCTAVE;
+
+/// A lock-free histogram of latency samples, expressed in microseconds.
+pub struct LatencyHistogram {
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_us: AtomicU64,
+    max_us: AtomicU64,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        let mut buckets = Vec::with_capacity(NUM_BUCKETS);
+        for _ in 0..NUM_BUCKETS {
+            buckets.push(AtomicU64::new(0));
+        }
+        Self {
+            buckets,
+            count: AtomicU64::new(0),
+            sum_us: AtomicU64::new(0),
+            max_us: AtomicU64::new(0),
+        }
+    }
+
+    /// Map a latency value to its bucket index.
+    fn bucket_index(value_us: u64) -> usize {
+        if value_us == 0 {
+            return 0;
+        }
+        let octave = std::cmp::min(63 - value_us.leading_zeros() as usize, NUM_OCTAVES - 1);
+        let octave_base = 1u64 << octave;
+        let step = std::cmp::max(octave_base / SUB_BUCKETS_PER_OCTAVE as u64, 1);
+        let sub = std::cmp::min(
+            ((value_us - octave_base) / step) as usize,
+            SUB_BUCKETS_PER_OCTAVE - 1,
+        );
+        octave * SUB_BUCKETS_PER_OCTAVE + sub
+    }
+
+    /// The inclusive upper bound of the values a bucket can hold, used as
+    /// the reported value for any percentile that falls into it.
+    fn bucket_upper_bound(index: usize) -> u64 {
+        let octave = index / SUB_BUCKETS_PER_OCTAVE;
+        let sub = index % SUB_BUCKETS_PER_OCTAVE;
+        let octave_base = 1u64 << octave;
+        let step = std::cmp::max(octave_base / SUB_BUCKETS_PER_OCTAVE as u64, 1);
+        octave_base + (sub as u64 + 1) * step - 1
+    }
+
+    /// Record a single latency sample, in microseconds. Lock-free: this only
+    /// performs a handful of atomic fetch-adds, so it is safe to call from
+    /// many worker threads without contending on a shared lock.
+    pub fn record(&self, value_us: u64) {
+        let index = Self::bucket_index(value_us);
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(value_us, Ordering::Relaxed);
+        self.raise_max(value_us);
+    }
+
+    fn raise_max(&self, value_us: u64) {
+        let mut observed = self.max_us.load(Ordering::Relaxed);
+        while value_us > observed {
+            match self.max_us.compare_exchange_weak(
+                observed,
+                value_us,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(previous) => observed = previous,
+            }
+        }
+    }
+
+    /// Return the latency (in microseconds) at quantile `q` (e.g. `0.99` for
+    /// p99), computed by walking the cumulative bucket counts.
+    pub fn percentile(&self, q: f64) -> u64 {
+        let total = self.count.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0;
+        }
+        let target = ((total as f64) * q).ceil() as u64;
+        let mut cumulative = 0u64;
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            cumulative += bucket.load(Ordering::Relaxed);
+            if cumulative >= target {
+                return Self::bucket_upper_bound(index);
+            }
+        }
+        self.max_us.load(Ordering::Relaxed)
+    }
+
+    pub fn max(&self) -> u64 {
+        self.max_us.load(Ordering::Relaxed)
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Merge another histogram's counts into this one. Used to combine
+    /// per-thread histograms into a single report at the end of a run.
+    pub fn merge(&self, other: &LatencyHistogram) {
+        for (mine, theirs) in self.buckets.iter().zip(other.buckets.iter()) {
+            mine.fetch_add(theirs.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.count
+            .fetch_add(other.count.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.sum_us
+            .fetch_add(other.sum_us.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.raise_max(other.max_us.load(Ordering::Relaxed));
+    }
+
+    /// Summarize p50/p90/p99/max latency plus achieved TPS, given the
+    /// wall-clock duration the samples were collected over.
+    pub fn summarize(&self, elapsed: Duration) -> LatencySummary {
+        let committed_txns = self.count();
+        let elapsed_secs = elapsed.as_millis() as f64 / 1000.0;
+        let tps = if elapsed_secs > 0.0 {
+            committed_txns as f64 / elapsed_secs
+        } else {
+            0.0
+        };
+        LatencySummary {
+            p50_us: self.percentile(0.50),
+            p90_us: self.percentile(0.90),
+            p99_us: self.percentile(0.99),
+            max_us: self.max(),
+            committed_txns,
+            tps,
+        }
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A snapshot summary of commit latencies and achieved throughput, suitable
+/// for printing at the end of a benchmark run.
+#[derive(Debug, Clone, Copy)]
+pub struct LatencySummary {
+    pub p50_us: u64,
+    pub p90_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+    pub committed_txns: u64,
+    pub tps: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_percentile() {
+        let histogram = LatencyHistogram::new();
+        for value_us in 1..=1000u64 {
+            histogram.record(value_us);
+        }
+        assert_eq!(histogram.count(), 1000);
+        assert_eq!(histogram.max(), 1000);
+        // p50 of a uniform 1..=1000 distribution should land close to 500.
+        let p50 = histogram.percentile(0.50);
+        assert!(p50 >= 495 && p50 <= 510, "p50 was {}", p50);
+        let p99 = histogram.percentile(0.99);
+        assert!(p99 >= 985 && p99 <= 1000, "p99 was {}", p99);
+    }
+
+    #[test]
+    fn test_merge() {
+        let first = LatencyHistogram::new();
+        let second = LatencyHistogram::new();
+        first.record(100);
+        second.record(200);
+        second.record(300);
+        first.merge(&second);
+        assert_eq!(first.count(), 3);
+        assert_eq!(first.max(), 300);
+    }
+}
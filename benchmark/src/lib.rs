@@ -0,0 +1,16 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use lazy_static::lazy_static;
+use metrics::OpMetrics;
+
+pub mod grpc_helpers;
+pub mod latency_histogram;
+pub mod load_generator;
+pub mod pipeline;
+
+pub use crate::grpc_helpers::divide_items;
+
+lazy_static! {
+    pub static ref OP_COUNTER: OpMetrics = OpMetrics::new_and_registered("benchmark");
+}
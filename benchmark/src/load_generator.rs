@@ -0,0 +1,302 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A sustained, target-TPS load emitter.
+//!
+//! Where `submit_and_wait_txn_requests` fires a single batch and blocks on
+//! its futures, `LoadGenerator` spawns a pool of worker threads, each owning
+//! a disjoint pool of sender accounts carved out via `divide_items`, and
+//! paces submissions with a token bucket so the aggregate offered rate stays
+//! flat at a configured target TPS for the full run duration. Workers keep
+//! their own local sequence numbers between submissions and only re-sync
+//! against the validator periodically, rather than after every batch.
+
+use crate::grpc_helpers::{
+    divide_items, submit_and_wait_txn_requests, sync_account_sequence_number_once,
+    SubmitTimeTracker,
+};
+use crate::latency_histogram::LatencyHistogram;
+use admission_control_proto::proto::{
+    admission_control::SubmitTransactionRequest, admission_control_grpc::AdmissionControlClient,
+};
+use logger::prelude::*;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use types::account_address::AccountAddress;
+
+/// How often a worker re-syncs its local view of senders' sequence numbers
+/// against the validator, rather than after every submitted batch.
+const RESYNC_INTERVAL: Duration = Duration::from_secs(5);
+/// How long a worker sleeps between its batch rounds.
+const TICK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Final attempted/accepted/committed tally of a load-generation run.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LoadSummary {
+    pub attempted: u64,
+    pub accepted: u64,
+    pub committed: u64,
+}
+
+/// A simple token bucket: tokens accrue continuously at `rate_per_sec` and
+/// callers block in `acquire` until one is free, which bounds the offered
+/// rate to `rate_per_sec`. Refilling is computed directly from wall-clock
+/// elapsed time against `rate_per_sec` (rather than a precomputed
+/// tokens-per-tick integer, which rounds any rate under one token per
+/// `TICK_INTERVAL` down to zero and then gets floored back up to one), so
+/// rates below `1_000 / TICK_INTERVAL.as_millis()` tokens/sec still accrue
+/// at their true rate instead of being silently over-emitted. Refilling is
+/// driven from `acquire` itself rather than from a single call per outer
+/// loop iteration, since a worker that owns more senders than it has tokens
+/// for must refill multiple times while draining one batch.
+struct TokenBucket {
+    tokens: AtomicU64,
+    rate_per_sec: u64,
+    last_refill: Mutex<Instant>,
+}
+
+impl TokenBucket {
+    fn new(rate_per_sec: u64) -> Self {
+        Self {
+            tokens: AtomicU64::new(0),
+            rate_per_sec,
+            last_refill: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Add tokens for however much real time has elapsed since the last
+    /// refill. Only the slice of elapsed time that actually bought a whole
+    /// token is consumed from `last_refill`; any fractional remainder is
+    /// left for the next call to accumulate, so no rate is lost to rounding.
+    fn refill(&self) {
+        let mut last_refill = self.last_refill.lock().unwrap();
+        let elapsed_ns = last_refill.elapsed().as_nanos();
+        let new_tokens = elapsed_ns * u128::from(self.rate_per_sec) / 1_000_000_000;
+        if new_tokens > 0 {
+            self.tokens.fetch_add(new_tokens as u64, Ordering::Relaxed);
+            let consumed_ns = new_tokens * 1_000_000_000 / u128::from(self.rate_per_sec);
+            *last_refill += Duration::from_nanos(consumed_ns as u64);
+        }
+    }
+
+    /// Block until a token is available, then consume it. Returns `false`
+    /// without consuming a token if `stop` is observed while waiting, so a
+    /// worker blocked here can still be interrupted by `LoadGenerator::stop`.
+    fn acquire(&self, stop: &AtomicBool) -> bool {
+        loop {
+            self.refill();
+            let available = self.tokens.load(Ordering::Relaxed);
+            if available > 0
+                && self
+                    .tokens
+                    .compare_exchange_weak(
+                        available,
+                        available - 1,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+            {
+                return true;
+            }
+            if stop.load(Ordering::Relaxed) {
+                return false;
+            }
+            thread::sleep(Duration::from_micros(100));
+        }
+    }
+}
+
+/// Drives transaction submission at a fixed aggregate target TPS by
+/// spawning a pool of worker threads, each owning a disjoint slice of
+/// sender accounts.
+pub struct LoadGenerator {
+    stop: Arc<AtomicBool>,
+    attempted: Arc<AtomicU64>,
+    accepted: Arc<AtomicU64>,
+    committed: Arc<AtomicU64>,
+    histogram: Arc<LatencyHistogram>,
+    handles: Vec<thread::JoinHandle<()>>,
+    start: Instant,
+}
+
+impl LoadGenerator {
+    /// Start emitting load against `clients` at `target_tps`, split evenly
+    /// across the worker threads, one per chunk that `divide_items` carves
+    /// out of `senders` for `num_workers` (so the thread count can differ
+    /// from `num_workers` when the split is uneven), each pinned round-robin
+    /// to one of `clients` so a multi-validator cluster is driven in parallel.
+    /// `generate_txn` builds a `SubmitTransactionRequest` for a given sender
+    /// at a given sequence number; it is called from worker threads, so it
+    /// must be `Send + Sync`. Call `stop` to end the run and collect a
+    /// summary.
+    pub fn start<F>(
+        clients: Vec<AdmissionControlClient>,
+        senders: Vec<(AccountAddress, u64)>,
+        num_workers: usize,
+        target_tps: u64,
+        generate_txn: F,
+    ) -> Self
+    where
+        F: Fn(AccountAddress, u64) -> SubmitTransactionRequest + Send + Sync + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let attempted = Arc::new(AtomicU64::new(0));
+        let accepted = Arc::new(AtomicU64::new(0));
+        let committed = Arc::new(AtomicU64::new(0));
+        let histogram = Arc::new(LatencyHistogram::new());
+        let generate_txn = Arc::new(generate_txn);
+
+        let chunks: Vec<Vec<(AccountAddress, u64)>> = divide_items(&senders, num_workers)
+            .map(<[(AccountAddress, u64)]>::to_vec)
+            .collect();
+        // One worker is spawned per chunk, so split the rate by the chunk
+        // count rather than `num_workers`, which it can differ from.
+        let per_worker_tps =
+            std::cmp::max(1, target_tps / std::cmp::max(1, chunks.len()) as u64);
+        let mut handles = Vec::with_capacity(chunks.len());
+        for (worker_id, worker_senders) in chunks.into_iter().enumerate() {
+            let client = clients[worker_id % clients.len()].clone();
+            let stop = stop.clone();
+            let attempted = attempted.clone();
+            let accepted = accepted.clone();
+            let committed = committed.clone();
+            let histogram = histogram.clone();
+            let generate_txn = generate_txn.clone();
+            let handle = thread::Builder::new()
+                .name(format!("load-worker-{}", worker_id))
+                .spawn(move || {
+                    run_worker(
+                        &client,
+                        worker_senders,
+                        per_worker_tps,
+                        &stop,
+                        &attempted,
+                        &accepted,
+                        &committed,
+                        &histogram,
+                        generate_txn.as_ref(),
+                    )
+                })
+                .expect("failed to spawn load generator worker");
+            handles.push(handle);
+        }
+
+        Self {
+            stop,
+            attempted,
+            accepted,
+            committed,
+            histogram,
+            handles,
+            start: Instant::now(),
+        }
+    }
+
+    /// Signal all workers to stop, wait for them to drain, and return the
+    /// final attempted/accepted/committed tally, the merged latency
+    /// histogram, and the run's wall-clock duration.
+    pub fn stop(self) -> (LoadSummary, LatencyHistogram, Duration) {
+        self.stop.store(true, Ordering::Relaxed);
+        for handle in self.handles {
+            let _ = handle.join();
+        }
+        let elapsed = self.start.elapsed();
+        let summary = LoadSummary {
+            attempted: self.attempted.load(Ordering::Relaxed),
+            accepted: self.accepted.load(Ordering::Relaxed),
+            committed: self.committed.load(Ordering::Relaxed),
+        };
+        let histogram = Arc::try_unwrap(self.histogram).unwrap_or_else(|shared| {
+            let merged = LatencyHistogram::new();
+            merged.merge(&shared);
+            merged
+        });
+        (summary, histogram, elapsed)
+    }
+}
+
+/// Body of a single worker thread: paced submission of its owned senders'
+/// transactions, with periodic re-sync against the validator.
+#[allow(clippy::too_many_arguments)]
+fn run_worker<F>(
+    client: &AdmissionControlClient,
+    mut senders: Vec<(AccountAddress, u64)>,
+    rate_per_sec: u64,
+    stop: &AtomicBool,
+    attempted: &AtomicU64,
+    accepted: &AtomicU64,
+    committed: &AtomicU64,
+    histogram: &LatencyHistogram,
+    generate_txn: &F,
+) where
+    F: Fn(AccountAddress, u64) -> SubmitTransactionRequest,
+{
+    let bucket = TokenBucket::new(rate_per_sec);
+    let submit_times = SubmitTimeTracker::new();
+    let mut last_resync = Instant::now();
+    // The last sequence number each sender was observed to have committed,
+    // used to derive `committed` from actual advancement rather than from
+    // the size of the resync response (which covers every sender regardless
+    // of commit status).
+    let mut last_committed: HashMap<AccountAddress, u64> =
+        senders.iter().map(|(address, seq)| (*address, *seq)).collect();
+
+    while !stop.load(Ordering::Relaxed) {
+        let mut batch = Vec::with_capacity(senders.len());
+        for (address, sequence_number) in senders.iter_mut() {
+            if !bucket.acquire(stop) {
+                break;
+            }
+            batch.push(generate_txn(*address, *sequence_number));
+            *sequence_number += 1;
+        }
+        if !batch.is_empty() {
+            attempted.fetch_add(batch.len() as u64, Ordering::Relaxed);
+            let responses =
+                submit_and_wait_txn_requests(std::slice::from_ref(client), &batch, &submit_times);
+            accepted.fetch_add(responses.len() as u64, Ordering::Relaxed);
+        }
+
+        if last_resync.elapsed() >= RESYNC_INTERVAL {
+            // A target-seeking, blocking sync would stall this worker for up
+            // to the full wait-for-commit timeout whenever the emitter is
+            // still outpacing commits, which is the common case under
+            // sustained load and would defeat the "offered rate stays flat"
+            // goal. Query each sender's real sequence number once instead.
+            let addresses: Vec<_> = senders.iter().map(|(address, _)| *address).collect();
+            let observed = sync_account_sequence_number_once(
+                std::slice::from_ref(client),
+                &addresses,
+                &submit_times,
+                histogram,
+            );
+            for (address, sequence_number) in senders.iter_mut() {
+                if let Some(observed_seq) = observed.get(address) {
+                    let previous = last_committed
+                        .insert(*address, *observed_seq)
+                        .unwrap_or(*observed_seq);
+                    if *observed_seq > previous {
+                        committed.fetch_add(*observed_seq - previous, Ordering::Relaxed);
+                    }
+                    // The validator's sequence number is authoritative: a
+                    // rejected submission already advanced our local nonce
+                    // without ever committing, which would desync this
+                    // sender forever if not repaired here.
+                    *sequence_number = *observed_seq;
+                }
+            }
+            debug!("Resynced {} senders against validator", observed.len());
+            last_resync = Instant::now();
+        }
+
+        thread::sleep(TICK_INTERVAL);
+    }
+}
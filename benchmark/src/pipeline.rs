@@ -0,0 +1,115 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A bounded producer/submitter/collector pipeline that overlaps transaction
+//! generation, submission, and response handling.
+//!
+//! Where `submit_and_wait_txn_requests` materializes every future up front
+//! and then calls `stream::futures_unordered(...).wait()`, running
+//! generation, submission, and collection as serial phases, `run_pipeline`
+//! threads them through bounded MPMC channels: a producer stage feeds
+//! `SubmitTransactionRequest`s onto a channel, a pool of submitter stages
+//! drain it through `submit_and_wait_txn_requests` and push the resulting
+//! responses onto a second channel, and a collector stage drains that while
+//! submission of later requests is still in flight. Bounded channel capacity
+//! caps in-flight memory and provides natural backpressure once a stage
+//! falls behind; queue-depth gauges surface a stall in any stage.
+
+use crate::grpc_helpers::{submit_and_wait_txn_requests, SubmitTimeTracker};
+use crate::OP_COUNTER;
+use admission_control_proto::proto::{
+    admission_control::{
+        SubmitTransactionRequest, SubmitTransactionResponse as ProtoSubmitTransactionResponse,
+    },
+    admission_control_grpc::AdmissionControlClient,
+};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use std::thread;
+
+/// Bounded channel capacity for both the request and response stages. Bounds
+/// in-flight memory and provides backpressure when a stage can't keep up.
+const PIPELINE_CHANNEL_CAPACITY: usize = 1_000;
+
+/// Final tally produced by draining a pipeline run to completion.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PipelineSummary {
+    pub submitted: u64,
+    pub accepted: u64,
+}
+
+/// Run `txn_requests` through a bounded producer/submitter/collector
+/// pipeline against `clients` instead of materializing every future up front.
+/// `num_submitters` submitter threads each drain the request channel through
+/// `submit_and_wait_txn_requests`, so later submissions overlap with the
+/// collector draining earlier responses.
+pub fn run_pipeline(
+    clients: Vec<AdmissionControlClient>,
+    txn_requests: Vec<SubmitTransactionRequest>,
+    num_submitters: usize,
+) -> PipelineSummary {
+    let (req_tx, req_rx): (Sender<SubmitTransactionRequest>, Receiver<SubmitTransactionRequest>) =
+        bounded(PIPELINE_CHANNEL_CAPACITY);
+    let (resp_tx, resp_rx): (
+        Sender<ProtoSubmitTransactionResponse>,
+        Receiver<ProtoSubmitTransactionResponse>,
+    ) = bounded(PIPELINE_CHANNEL_CAPACITY);
+
+    let num_requests = txn_requests.len() as u64;
+
+    // Producer stage: feed every request onto the bounded channel. `send`
+    // blocks once PIPELINE_CHANNEL_CAPACITY requests are in flight and no
+    // submitter has drained any yet, which is the pipeline's backpressure.
+    let producer = thread::Builder::new()
+        .name("pipeline-producer".to_string())
+        .spawn(move || {
+            for req in txn_requests {
+                OP_COUNTER.set("pipeline.queue_depth.requests", req_tx.len() as i64);
+                req_tx.send(req).expect("request channel disconnected");
+            }
+        })
+        .expect("failed to spawn pipeline producer");
+
+    // Submitter stages: drain the request channel and push accepted
+    // responses onto the response channel, overlapping submission of later
+    // requests with collection of earlier ones.
+    let mut submitters = Vec::with_capacity(num_submitters);
+    for submitter_id in 0..num_submitters {
+        let req_rx = req_rx.clone();
+        let resp_tx = resp_tx.clone();
+        let clients = clients.clone();
+        let handle = thread::Builder::new()
+            .name(format!("pipeline-submitter-{}", submitter_id))
+            .spawn(move || {
+                let submit_times = SubmitTimeTracker::new();
+                while let Ok(req) = req_rx.recv() {
+                    let responses = submit_and_wait_txn_requests(&clients, &[req], &submit_times);
+                    for resp in responses {
+                        OP_COUNTER.set("pipeline.queue_depth.responses", resp_tx.len() as i64);
+                        resp_tx.send(resp).expect("response channel disconnected");
+                    }
+                }
+            })
+            .expect("failed to spawn pipeline submitter");
+        submitters.push(handle);
+    }
+    // Drop the pipeline's own receiver/sender handles so each channel closes
+    // once the producer (for requests) or every submitter (for responses)
+    // finishes, letting the collector's iterator and the submitters' `recv`
+    // loops terminate naturally.
+    drop(req_rx);
+    drop(resp_tx);
+
+    // Collector stage: drain responses as they arrive, overlapped with
+    // ongoing submission.
+    let accepted = resp_rx.iter().count() as u64;
+
+    producer.join().expect("pipeline producer panicked");
+    for submitter in submitters {
+        submitter.join().expect("pipeline submitter panicked");
+    }
+
+    PipelineSummary {
+        submitted: num_requests,
+        accepted,
+    }
+}